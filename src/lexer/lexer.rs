@@ -1,11 +1,72 @@
-use anyhow::Result;
 use std::fmt::Display;
 
+/// A `0x`/`0o`/`0b` radix prefix: the base it denotes, and the predicate that
+/// recognizes a valid digit in that base.
+struct RadixPrefix {
+    radix: u32,
+    is_digit: fn(u8) -> bool,
+}
+
+impl RadixPrefix {
+    /// Matches the byte following a leading `0`, e.g. the `x` in `0x1F`.
+    fn for_byte(ch: u8) -> Option<RadixPrefix> {
+        match ch {
+            b'x' | b'X' => Some(RadixPrefix { radix: 16, is_digit: |c| c.is_ascii_hexdigit() }),
+            b'o' | b'O' => Some(RadixPrefix { radix: 8, is_digit: |c| (b'0'..=b'7').contains(&c) }),
+            b'b' | b'B' => Some(RadixPrefix { radix: 2, is_digit: |c| c == b'0' || c == b'1' }),
+            _ => None,
+        }
+    }
+}
+
+/// A byte range plus line/column information locating a token in the source.
+///
+/// `start`/`end` are byte offsets into the original input, `line` and `column`
+/// are both 1-indexed so they can be reported to a user directly.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+    pub line: usize,
+    pub column: usize,
+}
+
+/// A lexed token: the kind of token plus the span it came from in the source.
 #[derive(Debug, PartialEq)]
-pub enum TokenType {
-    Ident(String),
-    Int(String),
-    Illegal,
+pub struct Token<'src> {
+    pub kind: TokenType<'src>,
+    pub span: Span,
+}
+
+/// A recoverable lexing failure: the offending byte, where it was found, and
+/// a human-readable message. Lexing a single bad byte never panics; callers
+/// decide whether to stop at the first error ([`Lexer::next_token`]) or keep
+/// going and collect every error in the input ([`Lexer::tokenize`]).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LexError {
+    pub byte: u8,
+    pub span: Span,
+    pub message: String,
+}
+
+impl Display for LexError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} at {}:{}",
+            self.message, self.span.line, self.span.column
+        )
+    }
+}
+
+impl std::error::Error for LexError {}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TokenType<'src> {
+    Ident(&'src str),
+    Int(&'src str),
+    Float(String),
+    Str(String),
     Eof,
     Assign,
     Bang,
@@ -32,12 +93,13 @@ pub enum TokenType {
     False,
 }
 
-impl Display for TokenType {
+impl<'src> Display for TokenType<'src> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        return match self {
+        match self {
             TokenType::Ident(x) => write!(f, "Ident({})", x),
             TokenType::Int(x) => write!(f, "Int({})", x),
-            TokenType::Illegal => write!(f, "Illegal"),
+            TokenType::Float(x) => write!(f, "Float({})", x),
+            TokenType::Str(x) => write!(f, "Str({:?})", x),
             TokenType::Eof => write!(f, "Eof"),
             TokenType::Assign => write!(f, "Assign"),
             TokenType::Bang => write!(f, "Bang"),
@@ -62,24 +124,33 @@ impl Display for TokenType {
             TokenType::Return => write!(f, "Return"),
             TokenType::True => write!(f, "True"),
             TokenType::False => write!(f, "False"),
-        };
+        }
     }
 }
 
-pub struct Lexer {
-    input: Vec<u8>,
+/// Borrows the source text for the lifetime of the lexer instead of copying
+/// it, so identifiers and integers can be returned as `&'src str` slices
+/// into the original input rather than allocating a `String` per token.
+pub struct Lexer<'src> {
+    input: &'src [u8],
     position: usize,
     read_position: usize,
     ch: u8,
+    line: usize,
+    line_start: usize,
+    eof_emitted: bool,
 }
 
-impl Lexer {
-    pub fn new(input: String) -> Lexer {
+impl<'src> Lexer<'src> {
+    pub fn new(input: &'src str) -> Lexer<'src> {
         let mut lexer = Lexer {
-            input: input.into_bytes(),
+            input: input.as_bytes(),
             position: 0,
             read_position: 0,
             ch: 0,
+            line: 1,
+            line_start: 0,
+            eof_emitted: false,
         };
 
         lexer.read_char();
@@ -88,6 +159,11 @@ impl Lexer {
     }
 
     pub fn read_char(&mut self) {
+        if self.ch == b'\n' {
+            self.line += 1;
+            self.line_start = self.read_position;
+        }
+
         if self.read_position < self.input.len() {
             self.ch = self.input[self.read_position];
         } else {
@@ -98,10 +174,16 @@ impl Lexer {
         self.read_position += 1;
     }
 
-    pub fn next_token(&mut self) -> Result<TokenType> {
-        self.skip_whitespace();
+    pub fn next_token(&mut self) -> Result<Token<'src>, LexError> {
+        self.skip_trivia()?;
+
+        let start = self.position;
+        let line = self.line;
+        let column = start - self.line_start + 1;
+
+        let mut already_advanced = false;
 
-        let tok = match self.ch {
+        let kind = match self.ch {
             b'{' => TokenType::LSquirly,
             b'}' => TokenType::RSquirly,
             b'(' => TokenType::Lparen,
@@ -131,8 +213,9 @@ impl Lexer {
                 }
             },
             b'a'..=b'z' | b'A'..=b'Z' | b'_' => {
+                already_advanced = true;
                 let ident = self.read_ident();
-                return Ok(match ident.as_str() {
+                match ident {
                     "fn" => TokenType::Function,
                     "let" => TokenType::Let,
                     "if" => TokenType::If,
@@ -141,47 +224,318 @@ impl Lexer {
                     "return" => TokenType::Return,
                     "else" => TokenType::Else,
                     _ => TokenType::Ident(ident),
-                });
+                }
+            },
+            b'0'..=b'9' => {
+                already_advanced = true;
+                self.read_number()?
+            },
+            b'"' => {
+                already_advanced = true;
+                TokenType::Str(self.read_string(start, line, column)?)
             },
-            b'0'..=b'9' => return Ok(TokenType::Int(self.read_int())),
             0 => TokenType::Eof,
-            _ => unreachable!("no monkey program should contain these characters and you should feel bad about yourself")
+            other => {
+                let err = self.error_here(format!("unexpected character '{}'", other as char));
+                self.read_char();
+                return Err(err);
+            },
+        };
+
+        // `read_ident`/`read_number`/`read_string` already advance past the
+        // token themselves (including for keyword idents like `fn`/`let`);
+        // every other arm above still needs to consume its (possibly
+        // single-char) token.
+        if !already_advanced {
+            self.read_char();
+        }
+
+        let span = Span {
+            start,
+            end: self.position,
+            line,
+            column,
         };
 
-        self.read_char();
-        Ok(tok)
+        Ok(Token { kind, span })
     }
 
-    fn skip_whitespace(&mut self) {
-        while self.ch.is_ascii_whitespace() {
-            self.read_char();
+    /// Lexes the whole input, collecting every token and every error instead
+    /// of stopping at the first problem. On a bad byte, `next_token` already
+    /// skips past it, so lexing simply resumes at the next byte; this lets a
+    /// REPL or editor report *all* issues in one pass.
+    pub fn tokenize(&mut self) -> (Vec<Token<'src>>, Vec<LexError>) {
+        let mut tokens = Vec::new();
+        let mut errors = Vec::new();
+
+        for item in self.by_ref() {
+            match item {
+                Ok(tok) => tokens.push(tok),
+                Err(err) => errors.push(err),
+            }
+        }
+
+        (tokens, errors)
+    }
+
+    /// A named entry point for treating the lexer as a plain token stream,
+    /// e.g. `for tok in lexer.tokens() { ... }` or
+    /// `lexer.tokens().peekable()`.
+    pub fn tokens(self) -> impl Iterator<Item = Result<Token<'src>, LexError>> {
+        self
+    }
+
+    /// Builds a [`LexError`] located at the current (not-yet-consumed) char.
+    fn error_here(&self, message: impl Into<String>) -> LexError {
+        LexError {
+            byte: self.ch,
+            span: Span {
+                start: self.position,
+                end: self.position,
+                line: self.line,
+                column: self.position - self.line_start + 1,
+            },
+            message: message.into(),
+        }
+    }
+
+    /// Reads a `"..."` string literal starting at the opening quote,
+    /// processing `\n`, `\t`, `\r`, `\\`, `\"`, and `\u{XXXX}` escapes.
+    /// `start`/`line`/`column` locate the opening quote, for the error
+    /// message if the string is never closed.
+    fn read_string(&mut self, start: usize, line: usize, column: usize) -> Result<String, LexError> {
+        let unterminated = || LexError {
+            byte: 0,
+            span: Span { start, end: start + 1, line, column },
+            message: "unterminated string literal".to_string(),
+        };
+
+        self.read_char(); // consume opening quote
+        let mut buf: Vec<u8> = Vec::new();
+
+        loop {
+            match self.ch {
+                0 => return Err(unterminated()),
+                b'"' => {
+                    self.read_char(); // consume closing quote
+                    break;
+                },
+                b'\\' => {
+                    self.read_char();
+                    match self.ch {
+                        b'n' => buf.push(b'\n'),
+                        b't' => buf.push(b'\t'),
+                        b'r' => buf.push(b'\r'),
+                        b'\\' => buf.push(b'\\'),
+                        b'"' => buf.push(b'"'),
+                        b'u' => {
+                            self.read_char();
+                            if self.ch != b'{' {
+                                return Err(self.error_here("malformed unicode escape: expected '{' after \\u"));
+                            }
+                            self.read_char();
+
+                            let hex_start = self.position;
+                            while self.ch.is_ascii_hexdigit() {
+                                self.read_char();
+                            }
+
+                            if self.ch != b'}' {
+                                return Err(self.error_here("malformed unicode escape: expected closing '}'"));
+                            }
+
+                            let hex = String::from_utf8_lossy(&self.input[hex_start..self.position]).to_string();
+                            let code = u32::from_str_radix(&hex, 16).map_err(|_| {
+                                self.error_here(format!("malformed unicode escape '\\u{{{}}}'", hex))
+                            })?;
+                            let c = char::from_u32(code).ok_or_else(|| {
+                                self.error_here(format!("invalid unicode scalar value '\\u{{{}}}'", hex))
+                            })?;
+
+                            let mut encoded = [0u8; 4];
+                            buf.extend_from_slice(c.encode_utf8(&mut encoded).as_bytes());
+                        },
+                        0 => return Err(unterminated()),
+                        other => {
+                            return Err(self.error_here(format!("unknown escape sequence '\\{}' in string literal", other as char)));
+                        },
+                    }
+                    self.read_char();
+                },
+                c => {
+                    buf.push(c);
+                    self.read_char();
+                },
+            }
+        }
+
+        Ok(String::from_utf8_lossy(&buf).to_string())
+    }
+
+    /// Skips whitespace, `//` line comments, and `/* */` block comments, so
+    /// `next_token` never has to dispatch on any of them. Comments are pure
+    /// trivia to the parser, just like whitespace.
+    fn skip_trivia(&mut self) -> Result<(), LexError> {
+        loop {
+            if self.ch.is_ascii_whitespace() {
+                self.read_char();
+                continue;
+            }
+
+            if self.ch == b'/' && self.peek() == b'/' {
+                while self.ch != b'\n' && self.ch != 0 {
+                    self.read_char();
+                }
+                continue;
+            }
+
+            if self.ch == b'/' && self.peek() == b'*' {
+                let start = self.position;
+                let line = self.line;
+                let column = start - self.line_start + 1;
+
+                self.read_char(); // consume '/'
+                self.read_char(); // consume '*'
+
+                loop {
+                    if self.ch == 0 {
+                        return Err(LexError {
+                            byte: 0,
+                            span: Span { start, end: start + 2, line, column },
+                            message: "unterminated block comment".to_string(),
+                        });
+                    }
+
+                    if self.ch == b'*' && self.peek() == b'/' {
+                        self.read_char(); // consume '*'
+                        self.read_char(); // consume '/'
+                        break;
+                    }
+
+                    self.read_char();
+                }
+                continue;
+            }
+
+            return Ok(());
         }
     }
 
     fn peek(&self) -> u8 {
         if self.read_position >= self.input.len() {
-            return 0;
+            0
         } else {
-            return self.input[self.read_position];
+            self.input[self.read_position]
         }
     }
 
-    fn read_ident(&mut self) -> String {
+    fn read_ident(&mut self) -> &'src str {
         let pos = self.position;
         while self.ch.is_ascii_alphabetic() || self.ch == b'_' {
             self.read_char();
         }
 
-        return String::from_utf8_lossy(&self.input[pos..self.position]).to_string();
+        // Identifiers are ASCII-only, so this is always valid UTF-8.
+        std::str::from_utf8(&self.input[pos..self.position]).unwrap()
     }
 
-    fn read_int(&mut self) -> String {
+    /// Reads an integer or float literal starting at the current (digit)
+    /// char, including the `0x`/`0o`/`0b` radix prefixes and `1.5e-3`-style
+    /// floats. Returns the literal text wrapped in the right `TokenType`, or
+    /// an error if the literal is malformed (`0x` with no digits, `1.2.3`).
+    fn read_number(&mut self) -> Result<TokenType<'src>, LexError> {
         let pos = self.position;
+
+        if self.ch == b'0' {
+            if let Some(prefix) = RadixPrefix::for_byte(self.peek()) {
+                self.read_char(); // consume '0'
+                self.read_char(); // consume 'x'/'o'/'b'
+
+                let digits_start = self.position;
+                while (prefix.is_digit)(self.ch) {
+                    self.read_char();
+                }
+
+                if self.position == digits_start {
+                    return Err(self.error_here(format!(
+                        "malformed base-{} integer literal: no digits after prefix",
+                        prefix.radix
+                    )));
+                }
+
+                // Digits plus the radix prefix are ASCII-only, so this is
+                // always valid UTF-8.
+                let text = std::str::from_utf8(&self.input[pos..self.position]).unwrap();
+                return Ok(TokenType::Int(text));
+            }
+        }
+
         while self.ch.is_ascii_digit() {
             self.read_char();
         }
 
-        return String::from_utf8_lossy(&self.input[pos..self.position]).to_string();
+        let mut is_float = false;
+
+        if self.ch == b'.' && self.peek().is_ascii_digit() {
+            is_float = true;
+            self.read_char();
+            while self.ch.is_ascii_digit() {
+                self.read_char();
+            }
+        }
+
+        if self.ch == b'e' || self.ch == b'E' {
+            let mut lookahead = self.read_position;
+            if lookahead < self.input.len() && matches!(self.input[lookahead], b'+' | b'-') {
+                lookahead += 1;
+            }
+
+            if lookahead < self.input.len() && self.input[lookahead].is_ascii_digit() {
+                is_float = true;
+                self.read_char(); // consume 'e'/'E'
+                if self.ch == b'+' || self.ch == b'-' {
+                    self.read_char();
+                }
+                while self.ch.is_ascii_digit() {
+                    self.read_char();
+                }
+            }
+        }
+
+        if self.ch == b'.' {
+            let err = self.error_here("malformed numeric literal: too many decimal points");
+            self.read_char();
+            return Err(err);
+        }
+
+        // Digits, '.', 'e'/'E' and the sign are all ASCII, so this is always
+        // valid UTF-8.
+        let text = std::str::from_utf8(&self.input[pos..self.position]).unwrap();
+        if is_float {
+            Ok(TokenType::Float(text.to_string()))
+        } else {
+            Ok(TokenType::Int(text))
+        }
+    }
+}
+
+impl<'src> Iterator for Lexer<'src> {
+    type Item = Result<Token<'src>, LexError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.eof_emitted {
+            return None;
+        }
+
+        match self.next_token() {
+            Ok(tok) => {
+                if tok.kind == TokenType::Eof {
+                    self.eof_emitted = true;
+                }
+                Some(Ok(tok))
+            },
+            Err(err) => Some(Err(err)),
+        }
     }
 }
 
@@ -193,15 +547,202 @@ mod test {
 
     #[test]
     fn test_lexer() -> Result<()> {
-        let mut lexer = Lexer::new(String::from("1234567890"));
-        let mut next_token = lexer.next_token()
-            .map(|t| t)
-            .map_err(|err| err.into());
+        let mut lexer = Lexer::new("1234567890");
+
+        let tok = lexer.next_token()?;
+        assert_eq!(TokenType::Int("1234567890"), tok.kind);
+        assert_eq!(0, tok.span.start);
+        assert_eq!(10, tok.span.end);
+
+        let tok = lexer.next_token()?;
+        assert_eq!(TokenType::Eof, tok.kind);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_ident_borrows_from_source() -> Result<()> {
+        let source = String::from("foobar");
+        let mut lexer = Lexer::new(&source);
+
+        let tok = lexer.next_token()?;
+        match tok.kind {
+            TokenType::Ident(ident) => {
+                assert_eq!("foobar", ident);
+                // The slice must point back into `source`, not a fresh allocation.
+                assert_eq!(source.as_ptr(), ident.as_ptr());
+            },
+            other => panic!("expected Ident, got {:?}", other),
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_keyword_does_not_swallow_following_token() -> Result<()> {
+        let mut lexer = Lexer::new("true;");
+
+        assert_eq!(TokenType::True, lexer.next_token()?.kind);
+        assert_eq!(TokenType::Semicolon, lexer.next_token()?.kind);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_numeric_literals() -> Result<()> {
+        let mut lexer = Lexer::new("0x1F 0o17 0b1010 3.14 1e3");
+
+        assert_eq!(TokenType::Int("0x1F"), lexer.next_token()?.kind);
+        assert_eq!(TokenType::Int("0o17"), lexer.next_token()?.kind);
+        assert_eq!(TokenType::Int("0b1010"), lexer.next_token()?.kind);
+        assert_eq!(TokenType::Float(String::from("3.14")), lexer.next_token()?.kind);
+        assert_eq!(TokenType::Float(String::from("1e3")), lexer.next_token()?.kind);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_malformed_numeric_literals() {
+        assert!(Lexer::new("0x").next_token().is_err());
+        assert!(Lexer::new("1.2.3").next_token().is_err());
+    }
+
+    #[test]
+    fn test_malformed_numeric_literal_consumes_offending_dot() -> Result<()> {
+        let mut lexer = Lexer::new("1.2.3");
+
+        assert!(lexer.next_token().is_err());
+        // The offending second '.' must be consumed so lexing can make
+        // forward progress instead of reporting the same byte again.
+        assert_eq!(TokenType::Int("3"), lexer.next_token()?.kind);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_string_literals() -> Result<()> {
+        let mut lexer = Lexer::new(r#""hello\nworld" "\u{1F600}""#);
+
+        assert_eq!(
+            TokenType::Str(String::from("hello\nworld")),
+            lexer.next_token()?.kind
+        );
+        assert_eq!(TokenType::Str(String::from("\u{1F600}")), lexer.next_token()?.kind);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_unterminated_string_literal() {
+        assert!(Lexer::new("\"hello").next_token().is_err());
+    }
+
+    #[test]
+    fn test_span_tracks_line_and_column_across_newlines() -> Result<()> {
+        let mut lexer = Lexer::new("let x = 5;\nlet y\n= 10;");
+
+        let tok = lexer.next_token()?; // let
+        assert_eq!(1, tok.span.line);
+        assert_eq!(1, tok.span.column);
+
+        let tok = lexer.next_token()?; // x
+        assert_eq!(1, tok.span.line);
+        assert_eq!(5, tok.span.column);
+
+        let tok = lexer.next_token()?; // =
+        assert_eq!(1, tok.span.line);
+        assert_eq!(7, tok.span.column);
+
+        let tok = lexer.next_token()?; // 5
+        assert_eq!(1, tok.span.line);
+        assert_eq!(9, tok.span.column);
+
+        let tok = lexer.next_token()?; // ;
+        assert_eq!(1, tok.span.line);
+        assert_eq!(10, tok.span.column);
+
+        let tok = lexer.next_token()?; // let (line 2)
+        assert_eq!(2, tok.span.line);
+        assert_eq!(1, tok.span.column);
+
+        let tok = lexer.next_token()?; // y (line 2)
+        assert_eq!(2, tok.span.line);
+        assert_eq!(5, tok.span.column);
+
+        let tok = lexer.next_token()?; // = (line 3)
+        assert_eq!(3, tok.span.line);
+        assert_eq!(1, tok.span.column);
+
+        let tok = lexer.next_token()?; // 10 (line 3)
+        assert_eq!(3, tok.span.line);
+        assert_eq!(3, tok.span.column);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_unexpected_byte_does_not_panic() {
+        let err = Lexer::new("@").next_token().unwrap_err();
+        assert_eq!(b'@', err.byte);
+    }
+
+    #[test]
+    fn test_tokenize_collects_every_error_and_keeps_going() {
+        let mut lexer = Lexer::new("1 @ 2 # 3");
+        let (tokens, errors) = lexer.tokenize();
+
+        assert_eq!(2, errors.len());
         assert_eq!(
-            TokenType::Int(String::from("1234567890")),
-            next_token
+            vec![
+                TokenType::Int("1"),
+                TokenType::Int("2"),
+                TokenType::Int("3"),
+                TokenType::Eof,
+            ],
+            tokens.into_iter().map(|tok| tok.kind).collect::<Vec<_>>()
         );
-        assert_eq!(TokenType::Eof, next_token);
+    }
+
+    #[test]
+    fn test_lexer_as_iterator() -> Result<()> {
+        let lexer = Lexer::new("let x = 5;");
+        let tokens: Vec<TokenType> = lexer
+            .tokens()
+            .collect::<Result<Vec<_>, _>>()?
+            .into_iter()
+            .map(|tok| tok.kind)
+            .collect();
+
+        assert_eq!(
+            vec![
+                TokenType::Let,
+                TokenType::Ident("x"),
+                TokenType::Assign,
+                TokenType::Int("5"),
+                TokenType::Semicolon,
+                TokenType::Eof,
+            ],
+            tokens
+        );
+
         Ok(())
     }
+
+    #[test]
+    fn test_skips_line_and_block_comments() -> Result<()> {
+        let mut lexer = Lexer::new("// a leading comment\nlet /* inline */ x = 5;");
+
+        assert_eq!(TokenType::Let, lexer.next_token()?.kind);
+        assert_eq!(TokenType::Ident("x"), lexer.next_token()?.kind);
+        assert_eq!(TokenType::Assign, lexer.next_token()?.kind);
+        assert_eq!(TokenType::Int("5"), lexer.next_token()?.kind);
+        assert_eq!(TokenType::Semicolon, lexer.next_token()?.kind);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_unterminated_block_comment() {
+        assert!(Lexer::new("/* never closed").next_token().is_err());
+    }
 }
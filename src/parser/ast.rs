@@ -0,0 +1,166 @@
+use std::fmt::{self, Display};
+
+/// An identifier, e.g. the `x` in `let x = 5;`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Identifier {
+    pub name: String,
+}
+
+impl Display for Identifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+/// A `{ ... }` block of statements, e.g. the body of an `if` or `fn`.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct BlockStatement {
+    pub statements: Vec<Statement>,
+}
+
+impl Display for BlockStatement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for stmt in &self.statements {
+            write!(f, "{}", stmt)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct IfExpression {
+    pub condition: Box<Expression>,
+    pub consequence: BlockStatement,
+    pub alternative: Option<BlockStatement>,
+}
+
+impl Display for IfExpression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "if{} {{ {} }}", self.condition, self.consequence)?;
+        if let Some(alternative) = &self.alternative {
+            write!(f, " else {{ {} }}", alternative)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FunctionLiteral {
+    pub parameters: Vec<Identifier>,
+    pub body: BlockStatement,
+}
+
+impl Display for FunctionLiteral {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let params = self.parameters.iter().map(|p| p.name.clone()).collect::<Vec<_>>().join(", ");
+        write!(f, "fn({}) {{ {} }}", params, self.body)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CallExpression {
+    pub function: Box<Expression>,
+    pub arguments: Vec<Expression>,
+}
+
+impl Display for CallExpression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let args = self.arguments.iter().map(|a| a.to_string()).collect::<Vec<_>>().join(", ");
+        write!(f, "{}({})", self.function, args)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expression {
+    Identifier(Identifier),
+    IntegerLiteral(i64),
+    FloatLiteral(f64),
+    StringLiteral(String),
+    BooleanLiteral(bool),
+    Prefix {
+        operator: String,
+        right: Box<Expression>,
+    },
+    Infix {
+        left: Box<Expression>,
+        operator: String,
+        right: Box<Expression>,
+    },
+    If(IfExpression),
+    Function(FunctionLiteral),
+    Call(CallExpression),
+}
+
+impl Display for Expression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Expression::Identifier(ident) => write!(f, "{}", ident),
+            Expression::IntegerLiteral(value) => write!(f, "{}", value),
+            Expression::FloatLiteral(value) => write!(f, "{}", value),
+            Expression::StringLiteral(value) => write!(f, "{:?}", value),
+            Expression::BooleanLiteral(value) => write!(f, "{}", value),
+            Expression::Prefix { operator, right } => write!(f, "({}{})", operator, right),
+            Expression::Infix { left, operator, right } => write!(f, "({} {} {})", left, operator, right),
+            Expression::If(if_expr) => write!(f, "{}", if_expr),
+            Expression::Function(func) => write!(f, "{}", func),
+            Expression::Call(call) => write!(f, "{}", call),
+        }
+    }
+}
+
+/// `let <name> = <value>;`
+#[derive(Debug, Clone, PartialEq)]
+pub struct LetStatement {
+    pub name: Identifier,
+    pub value: Expression,
+}
+
+impl Display for LetStatement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "let {} = {};", self.name, self.value)
+    }
+}
+
+/// `return <value>;`
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReturnStatement {
+    pub value: Expression,
+}
+
+impl Display for ReturnStatement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "return {};", self.value)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Statement {
+    Let(LetStatement),
+    Return(ReturnStatement),
+    Expression(Expression),
+}
+
+impl Display for Statement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Statement::Let(stmt) => write!(f, "{}", stmt),
+            Statement::Return(stmt) => write!(f, "{}", stmt),
+            Statement::Expression(expr) => write!(f, "{}", expr),
+        }
+    }
+}
+
+/// The root AST node: a whole program is just a sequence of statements.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Program {
+    pub statements: Vec<Statement>,
+}
+
+impl Display for Program {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for stmt in &self.statements {
+            write!(f, "{}", stmt)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,483 @@
+pub mod ast;
+
+use crate::lexer::lexer::{Lexer, LexError, Span, Token, TokenType};
+use ast::{
+    BlockStatement, CallExpression, Expression, FunctionLiteral, Identifier, IfExpression, LetStatement, Program,
+    ReturnStatement, Statement,
+};
+
+/// A parsing failure: a message plus the span of the token that triggered it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+    pub span: Span,
+    pub message: String,
+}
+
+/// Operator-precedence levels for the Pratt parser, lowest to highest.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Eq, Ord)]
+enum Precedence {
+    Lowest,
+    Equals,      // == !=
+    LessGreater, // > <
+    Sum,         // + -
+    Product,     // * /
+    Prefix,      // -x !x
+    Call,        // fn(x)
+}
+
+fn precedence_of(kind: &TokenType<'_>) -> Precedence {
+    match kind {
+        TokenType::Equal | TokenType::NotEqual => Precedence::Equals,
+        TokenType::LessThan | TokenType::GreaterThan => Precedence::LessGreater,
+        TokenType::Plus | TokenType::Dash => Precedence::Sum,
+        TokenType::Asterisk | TokenType::ForwardSlash => Precedence::Product,
+        TokenType::Lparen => Precedence::Call,
+        _ => Precedence::Lowest,
+    }
+}
+
+/// The literal text of an operator token, for use in `Expression::Prefix`/`Infix` nodes.
+fn operator_literal(kind: &TokenType<'_>) -> &'static str {
+    match kind {
+        TokenType::Bang => "!",
+        TokenType::Dash => "-",
+        TokenType::Plus => "+",
+        TokenType::Asterisk => "*",
+        TokenType::ForwardSlash => "/",
+        TokenType::Equal => "==",
+        TokenType::NotEqual => "!=",
+        TokenType::LessThan => "<",
+        TokenType::GreaterThan => ">",
+        other => unreachable!("{} is not an operator token", other),
+    }
+}
+
+/// A Pratt (top-down operator precedence) parser over a [`Lexer`]'s token
+/// stream. Never panics on malformed input: both lex errors and parse errors
+/// are accumulated in `errors()` so a caller can report everything wrong
+/// with a program in one pass, the same way [`Lexer::tokenize`] does.
+pub struct Parser<'src> {
+    lexer: Lexer<'src>,
+    cur_token: Token<'src>,
+    peek_token: Token<'src>,
+    errors: Vec<ParseError>,
+}
+
+impl<'src> Parser<'src> {
+    pub fn new(mut lexer: Lexer<'src>) -> Self {
+        let mut errors = Vec::new();
+        let cur_token = Self::read_token(&mut lexer, &mut errors);
+        let peek_token = Self::read_token(&mut lexer, &mut errors);
+
+        Parser { lexer, cur_token, peek_token, errors }
+    }
+
+    pub fn errors(&self) -> &[ParseError] {
+        &self.errors
+    }
+
+    pub fn parse_program(&mut self) -> Program {
+        let mut program = Program::default();
+
+        while self.cur_token.kind != TokenType::Eof {
+            if let Some(stmt) = self.parse_statement() {
+                program.statements.push(stmt);
+            }
+            self.next_token();
+        }
+
+        program
+    }
+
+    /// Pulls the next token out of the lexer, recording (and skipping past)
+    /// any lex errors so a single bad byte can't wedge the parser.
+    fn read_token(lexer: &mut Lexer<'src>, errors: &mut Vec<ParseError>) -> Token<'src> {
+        loop {
+            match lexer.next_token() {
+                Ok(tok) => return tok,
+                Err(LexError { span, message, .. }) => errors.push(ParseError { span, message }),
+            }
+        }
+    }
+
+    fn next_token(&mut self) {
+        let next = Self::read_token(&mut self.lexer, &mut self.errors);
+        self.cur_token = std::mem::replace(&mut self.peek_token, next);
+    }
+
+    fn parse_statement(&mut self) -> Option<Statement> {
+        match &self.cur_token.kind {
+            TokenType::Let => self.parse_let_statement(),
+            TokenType::Return => self.parse_return_statement(),
+            _ => self.parse_expression_statement(),
+        }
+    }
+
+    fn parse_let_statement(&mut self) -> Option<Statement> {
+        let name = match &self.peek_token.kind {
+            TokenType::Ident(name) => name.to_string(),
+            other => {
+                self.peek_error("an identifier", &other.clone());
+                return None;
+            },
+        };
+        self.next_token(); // cur_token is now the identifier
+
+        if !self.expect_peek(TokenType::Assign) {
+            return None;
+        }
+        self.next_token(); // cur_token is now the first token of the value
+
+        let value = self.parse_expression(Precedence::Lowest)?;
+
+        if self.peek_token.kind == TokenType::Semicolon {
+            self.next_token();
+        }
+
+        Some(Statement::Let(LetStatement { name: Identifier { name }, value }))
+    }
+
+    fn parse_return_statement(&mut self) -> Option<Statement> {
+        self.next_token(); // cur_token is now the first token of the value
+
+        let value = self.parse_expression(Precedence::Lowest)?;
+
+        if self.peek_token.kind == TokenType::Semicolon {
+            self.next_token();
+        }
+
+        Some(Statement::Return(ReturnStatement { value }))
+    }
+
+    fn parse_expression_statement(&mut self) -> Option<Statement> {
+        let expr = self.parse_expression(Precedence::Lowest)?;
+
+        if self.peek_token.kind == TokenType::Semicolon {
+            self.next_token();
+        }
+
+        Some(Statement::Expression(expr))
+    }
+
+    fn parse_block_statement(&mut self) -> BlockStatement {
+        let mut block = BlockStatement::default();
+        self.next_token(); // step past the opening '{'
+
+        while self.cur_token.kind != TokenType::RSquirly && self.cur_token.kind != TokenType::Eof {
+            if let Some(stmt) = self.parse_statement() {
+                block.statements.push(stmt);
+            }
+            self.next_token();
+        }
+
+        block
+    }
+
+    fn parse_expression(&mut self, precedence: Precedence) -> Option<Expression> {
+        let mut left = self.parse_prefix()?;
+
+        while self.peek_token.kind != TokenType::Semicolon && precedence < precedence_of(&self.peek_token.kind) {
+            self.next_token();
+            left = match &self.cur_token.kind {
+                TokenType::Lparen => self.parse_call_expression(left)?,
+                _ => self.parse_infix_expression(left)?,
+            };
+        }
+
+        Some(left)
+    }
+
+    fn parse_prefix(&mut self) -> Option<Expression> {
+        match self.cur_token.kind.clone() {
+            TokenType::Ident(name) => Some(Expression::Identifier(Identifier { name: name.to_string() })),
+            TokenType::Int(text) => self.parse_integer_literal(text),
+            TokenType::Float(text) => self.parse_float_literal(&text),
+            TokenType::Str(value) => Some(Expression::StringLiteral(value)),
+            TokenType::True => Some(Expression::BooleanLiteral(true)),
+            TokenType::False => Some(Expression::BooleanLiteral(false)),
+            TokenType::Bang | TokenType::Dash => self.parse_prefix_operator_expression(),
+            TokenType::Lparen => self.parse_grouped_expression(),
+            TokenType::If => self.parse_if_expression(),
+            TokenType::Function => self.parse_function_literal(),
+            other => {
+                self.error(format!("no prefix parse function for {}", other));
+                None
+            },
+        }
+    }
+
+    fn parse_integer_literal(&mut self, text: &str) -> Option<Expression> {
+        let (digits, radix) = match text.get(..2) {
+            Some("0x") | Some("0X") => (&text[2..], 16),
+            Some("0o") | Some("0O") => (&text[2..], 8),
+            Some("0b") | Some("0B") => (&text[2..], 2),
+            _ => (text, 10),
+        };
+
+        match i64::from_str_radix(digits, radix) {
+            Ok(value) => Some(Expression::IntegerLiteral(value)),
+            Err(_) => {
+                self.error(format!("could not parse '{}' as an integer", text));
+                None
+            },
+        }
+    }
+
+    fn parse_float_literal(&mut self, text: &str) -> Option<Expression> {
+        match text.parse::<f64>() {
+            Ok(value) => Some(Expression::FloatLiteral(value)),
+            Err(_) => {
+                self.error(format!("could not parse '{}' as a float", text));
+                None
+            },
+        }
+    }
+
+    fn parse_prefix_operator_expression(&mut self) -> Option<Expression> {
+        let operator = operator_literal(&self.cur_token.kind).to_string();
+        self.next_token();
+        let right = self.parse_expression(Precedence::Prefix)?;
+        Some(Expression::Prefix { operator, right: Box::new(right) })
+    }
+
+    fn parse_infix_expression(&mut self, left: Expression) -> Option<Expression> {
+        let operator = operator_literal(&self.cur_token.kind).to_string();
+        let precedence = precedence_of(&self.cur_token.kind);
+        self.next_token();
+        let right = self.parse_expression(precedence)?;
+        Some(Expression::Infix { left: Box::new(left), operator, right: Box::new(right) })
+    }
+
+    fn parse_grouped_expression(&mut self) -> Option<Expression> {
+        self.next_token(); // step past '('
+        let expr = self.parse_expression(Precedence::Lowest)?;
+
+        if !self.expect_peek(TokenType::Rparen) {
+            return None;
+        }
+
+        Some(expr)
+    }
+
+    fn parse_if_expression(&mut self) -> Option<Expression> {
+        if !self.expect_peek(TokenType::Lparen) {
+            return None;
+        }
+        self.next_token(); // step past '('
+
+        let condition = self.parse_expression(Precedence::Lowest)?;
+
+        if !self.expect_peek(TokenType::Rparen) {
+            return None;
+        }
+        if !self.expect_peek(TokenType::LSquirly) {
+            return None;
+        }
+
+        let consequence = self.parse_block_statement();
+
+        let alternative = if self.peek_token.kind == TokenType::Else {
+            self.next_token();
+            if !self.expect_peek(TokenType::LSquirly) {
+                return None;
+            }
+            Some(self.parse_block_statement())
+        } else {
+            None
+        };
+
+        Some(Expression::If(IfExpression { condition: Box::new(condition), consequence, alternative }))
+    }
+
+    fn parse_function_literal(&mut self) -> Option<Expression> {
+        if !self.expect_peek(TokenType::Lparen) {
+            return None;
+        }
+
+        let parameters = self.parse_function_parameters()?;
+
+        if !self.expect_peek(TokenType::LSquirly) {
+            return None;
+        }
+
+        let body = self.parse_block_statement();
+
+        Some(Expression::Function(FunctionLiteral { parameters, body }))
+    }
+
+    fn parse_function_parameters(&mut self) -> Option<Vec<Identifier>> {
+        let mut parameters = Vec::new();
+
+        if self.peek_token.kind == TokenType::Rparen {
+            self.next_token();
+            return Some(parameters);
+        }
+
+        self.next_token();
+        parameters.push(self.parse_parameter()?);
+
+        while self.peek_token.kind == TokenType::Comma {
+            self.next_token();
+            self.next_token();
+            parameters.push(self.parse_parameter()?);
+        }
+
+        if !self.expect_peek(TokenType::Rparen) {
+            return None;
+        }
+
+        Some(parameters)
+    }
+
+    fn parse_parameter(&mut self) -> Option<Identifier> {
+        match &self.cur_token.kind {
+            TokenType::Ident(name) => Some(Identifier { name: name.to_string() }),
+            other => {
+                self.error(format!("expected a parameter name, got {}", other));
+                None
+            },
+        }
+    }
+
+    fn parse_call_expression(&mut self, function: Expression) -> Option<Expression> {
+        let arguments = self.parse_call_arguments()?;
+        Some(Expression::Call(CallExpression { function: Box::new(function), arguments }))
+    }
+
+    fn parse_call_arguments(&mut self) -> Option<Vec<Expression>> {
+        let mut arguments = Vec::new();
+
+        if self.peek_token.kind == TokenType::Rparen {
+            self.next_token();
+            return Some(arguments);
+        }
+
+        self.next_token();
+        arguments.push(self.parse_expression(Precedence::Lowest)?);
+
+        while self.peek_token.kind == TokenType::Comma {
+            self.next_token();
+            self.next_token();
+            arguments.push(self.parse_expression(Precedence::Lowest)?);
+        }
+
+        if !self.expect_peek(TokenType::Rparen) {
+            return None;
+        }
+
+        Some(arguments)
+    }
+
+    /// If `peek_token` is `expected`, advances and returns `true`; otherwise
+    /// records a parse error and returns `false` without advancing.
+    fn expect_peek(&mut self, expected: TokenType<'src>) -> bool {
+        if self.peek_token.kind == expected {
+            self.next_token();
+            true
+        } else {
+            let message = format!("expected next token to be {}, got {} instead", expected, self.peek_token.kind);
+            self.errors.push(ParseError { span: self.peek_token.span, message });
+            false
+        }
+    }
+
+    fn peek_error(&mut self, expected: &str, got: &TokenType<'_>) {
+        let message = format!("expected {}, got {} instead", expected, got);
+        self.errors.push(ParseError { span: self.peek_token.span, message });
+    }
+
+    fn error(&mut self, message: String) {
+        self.errors.push(ParseError { span: self.cur_token.span, message });
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn parse(input: &str) -> (Program, Vec<ParseError>) {
+        let lexer = Lexer::new(input);
+        let mut parser = Parser::new(lexer);
+        let program = parser.parse_program();
+        (program, parser.errors().to_vec())
+    }
+
+    #[test]
+    fn test_let_statements() {
+        let (program, errors) = parse("let x = 5;\nlet y = 10;\nlet foobar = x;");
+        assert!(errors.is_empty(), "unexpected parser errors: {:?}", errors);
+        assert_eq!(3, program.statements.len());
+
+        let names: Vec<&str> = program
+            .statements
+            .iter()
+            .map(|stmt| match stmt {
+                Statement::Let(let_stmt) => let_stmt.name.name.as_str(),
+                other => panic!("expected a let statement, got {:?}", other),
+            })
+            .collect();
+        assert_eq!(vec!["x", "y", "foobar"], names);
+    }
+
+    #[test]
+    fn test_return_statement() {
+        let (program, errors) = parse("return 5;");
+        assert!(errors.is_empty(), "unexpected parser errors: {:?}", errors);
+        assert_eq!(1, program.statements.len());
+        assert!(matches!(program.statements[0], Statement::Return(_)));
+    }
+
+    #[test]
+    fn test_operator_precedence() {
+        let (program, errors) = parse("a + b * c == d / e - f");
+        assert!(errors.is_empty(), "unexpected parser errors: {:?}", errors);
+        assert_eq!(1, program.statements.len());
+        assert_eq!("((a + (b * c)) == ((d / e) - f))", program.statements[0].to_string());
+    }
+
+    #[test]
+    fn test_if_expression() {
+        let (program, errors) = parse("if (x < y) { x } else { y }");
+        assert!(errors.is_empty(), "unexpected parser errors: {:?}", errors);
+        assert_eq!(1, program.statements.len());
+
+        match &program.statements[0] {
+            Statement::Expression(Expression::If(if_expr)) => {
+                assert_eq!("(x < y)", if_expr.condition.to_string());
+                assert!(if_expr.alternative.is_some());
+            },
+            other => panic!("expected an if expression, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_function_literal_and_call() {
+        let (program, errors) = parse("let add = fn(x, y) { x + y }; add(1, 2 * 3);");
+        assert!(errors.is_empty(), "unexpected parser errors: {:?}", errors);
+        assert_eq!(2, program.statements.len());
+
+        match &program.statements[0] {
+            Statement::Let(let_stmt) => match &let_stmt.value {
+                Expression::Function(func) => {
+                    assert_eq!(vec!["x", "y"], func.parameters.iter().map(|p| p.name.as_str()).collect::<Vec<_>>());
+                },
+                other => panic!("expected a function literal, got {:?}", other),
+            },
+            other => panic!("expected a let statement, got {:?}", other),
+        }
+
+        match &program.statements[1] {
+            Statement::Expression(Expression::Call(call)) => {
+                assert_eq!("add", call.function.to_string());
+                assert_eq!(2, call.arguments.len());
+            },
+            other => panic!("expected a call expression, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_malformed_let_statement_is_recorded_not_panicked() {
+        let (_, errors) = parse("let = 5;");
+        assert!(!errors.is_empty());
+    }
+}